@@ -0,0 +1,237 @@
+use crate::{Error, PublicKey};
+use ed25519_dalek::Signature;
+
+/// Capability flags that a `Certificate` may grant to a delegated subkey.
+///
+/// A certificate with no flags set authorizes nothing; flags are combined
+/// with bitwise-or.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DelegationCapabilities(pub u32);
+
+impl DelegationCapabilities {
+    /// No capabilities
+    pub const NONE: DelegationCapabilities = DelegationCapabilities(0);
+
+    /// Authorizes the subkey to sign records on behalf of the master key
+    pub const SIGN_RECORDS: DelegationCapabilities = DelegationCapabilities(1 << 0);
+
+    /// Authorizes the subkey to issue further delegation certificates
+    pub const DELEGATE: DelegationCapabilities = DelegationCapabilities(1 << 1);
+
+    /// Does this set of capabilities include all of `other`?
+    #[must_use]
+    pub fn contains(&self, other: DelegationCapabilities) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for DelegationCapabilities {
+    type Output = DelegationCapabilities;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        DelegationCapabilities(self.0 | rhs.0)
+    }
+}
+
+/// A certificate binding a subkey to a master key, optionally restricted to
+/// a validity window and a set of capabilities, signed by the master key.
+///
+/// This lets a server or user issue short-lived operational subkeys while
+/// keeping the master key offline: downstream record validation can accept
+/// a record signed by a subkey as long as a `Certificate` from the master
+/// key `authorizes` it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Certificate {
+    pub(crate) master: PublicKey,
+    pub(crate) subkey: PublicKey,
+    pub(crate) capabilities: DelegationCapabilities,
+    pub(crate) valid_range: Option<(u64, u64)>,
+    pub(crate) signature: Signature,
+}
+
+impl Certificate {
+    /// The canonical byte form of a certificate's claims, i.e. everything
+    /// the master key signs over. This excludes the signature itself.
+    pub(crate) fn canonical_bytes(
+        master: &PublicKey,
+        subkey: &PublicKey,
+        capabilities: DelegationCapabilities,
+        valid_range: Option<(u64, u64)>,
+    ) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(32 + 32 + 4 + 1 + 8 + 8);
+        bytes.extend_from_slice(master.as_bytes());
+        bytes.extend_from_slice(subkey.as_bytes());
+        bytes.extend_from_slice(&capabilities.0.to_le_bytes());
+        let (present, start, end) = match valid_range {
+            Some((start, end)) => (1u8, start, end),
+            None => (0u8, 0u64, 0u64),
+        };
+        bytes.push(present);
+        bytes.extend_from_slice(&start.to_le_bytes());
+        bytes.extend_from_slice(&end.to_le_bytes());
+        bytes
+    }
+
+    /// The master key that issued this certificate
+    #[must_use]
+    pub fn master(&self) -> PublicKey {
+        self.master
+    }
+
+    /// The subkey this certificate binds to the master key
+    #[must_use]
+    pub fn subkey(&self) -> PublicKey {
+        self.subkey
+    }
+
+    /// The capabilities this certificate grants to the subkey
+    #[must_use]
+    pub fn capabilities(&self) -> DelegationCapabilities {
+        self.capabilities
+    }
+
+    /// The inclusive `(start, end)` unix-second validity window, if this
+    /// certificate is time-limited
+    #[must_use]
+    pub fn valid_range(&self) -> Option<(u64, u64)> {
+        self.valid_range
+    }
+
+    /// Verify the master key's signature over this certificate's claims
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the signature is invalid
+    pub fn verify(&self) -> Result<(), Error> {
+        let bytes =
+            Self::canonical_bytes(&self.master, &self.subkey, self.capabilities, self.valid_range);
+        self.master.verify(&bytes, &self.signature)
+    }
+
+    /// Does this certificate, with a valid master signature, authorize `subkey`
+    /// to exercise `required` capabilities at unix-second time `at`?
+    ///
+    /// This checks `capabilities` and `valid_range` as well as the
+    /// signature: a certificate that does not grant `required` (e.g. one
+    /// minted with only `DelegationCapabilities::DELEGATE`, for a caller
+    /// asking about `SIGN_RECORDS`), or that is expired or not yet valid,
+    /// does not authorize, even if the signature itself checks out. Callers
+    /// validating incoming records MUST pass the capability they actually
+    /// need and the time the record claims to have been signed (or the
+    /// current time), not skip either check.
+    #[must_use]
+    pub fn authorizes(
+        &self,
+        subkey: &PublicKey,
+        required: DelegationCapabilities,
+        at: u64,
+    ) -> bool {
+        if self.subkey != *subkey {
+            return false;
+        }
+        if !self.capabilities.contains(required) {
+            return false;
+        }
+        if let Some((start, end)) = self.valid_range {
+            if at < start || at > end {
+                return false;
+            }
+        }
+        self.verify().is_ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PrivateKey;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_delegation() {
+        let mut csprng = OsRng;
+
+        let master = PrivateKey::generate(&mut csprng);
+        let subkey = PrivateKey::generate(&mut csprng);
+        let other = PrivateKey::generate(&mut csprng);
+
+        let cert = master.delegate_to(
+            subkey.public(),
+            DelegationCapabilities::SIGN_RECORDS | DelegationCapabilities::DELEGATE,
+            Some((1_000, 2_000)),
+        );
+
+        assert_eq!(cert.master(), master.public());
+        assert_eq!(cert.subkey(), subkey.public());
+        assert_eq!(cert.valid_range(), Some((1_000, 2_000)));
+        assert!(cert.capabilities().contains(DelegationCapabilities::SIGN_RECORDS));
+        assert!(cert.verify().is_ok());
+        assert!(cert.authorizes(&subkey.public(), DelegationCapabilities::SIGN_RECORDS, 1_500));
+        assert!(!cert.authorizes(&other.public(), DelegationCapabilities::SIGN_RECORDS, 1_500));
+
+        // A certificate claiming a different subkey, using an otherwise
+        // identical signature, must not verify.
+        let mut forged = cert;
+        forged.subkey = other.public();
+        assert!(forged.verify().is_err());
+        assert!(!forged.authorizes(&other.public(), DelegationCapabilities::SIGN_RECORDS, 1_500));
+    }
+
+    #[test]
+    fn test_delegation_capability_gating() {
+        let mut csprng = OsRng;
+
+        let master = PrivateKey::generate(&mut csprng);
+        let subkey = PrivateKey::generate(&mut csprng);
+
+        // A certificate minted with no capabilities authorizes nothing.
+        let bare = master.delegate_to(subkey.public(), DelegationCapabilities::NONE, None);
+        assert!(!bare.authorizes(&subkey.public(), DelegationCapabilities::SIGN_RECORDS, 0));
+        assert!(!bare.authorizes(&subkey.public(), DelegationCapabilities::DELEGATE, 0));
+
+        // A certificate granting only DELEGATE does not authorize SIGN_RECORDS.
+        let delegate_only =
+            master.delegate_to(subkey.public(), DelegationCapabilities::DELEGATE, None);
+        assert!(!delegate_only.authorizes(&subkey.public(), DelegationCapabilities::SIGN_RECORDS, 0));
+        assert!(delegate_only.authorizes(&subkey.public(), DelegationCapabilities::DELEGATE, 0));
+
+        // A certificate granting both authorizes each individually and together.
+        let both = master.delegate_to(
+            subkey.public(),
+            DelegationCapabilities::SIGN_RECORDS | DelegationCapabilities::DELEGATE,
+            None,
+        );
+        assert!(both.authorizes(&subkey.public(), DelegationCapabilities::SIGN_RECORDS, 0));
+        assert!(both.authorizes(&subkey.public(), DelegationCapabilities::DELEGATE, 0));
+        assert!(both.authorizes(
+            &subkey.public(),
+            DelegationCapabilities::SIGN_RECORDS | DelegationCapabilities::DELEGATE,
+            0
+        ));
+    }
+
+    #[test]
+    fn test_delegation_validity_window() {
+        let mut csprng = OsRng;
+
+        let master = PrivateKey::generate(&mut csprng);
+        let subkey = PrivateKey::generate(&mut csprng);
+
+        let cert = master.delegate_to(
+            subkey.public(),
+            DelegationCapabilities::SIGN_RECORDS,
+            Some((1_000, 2_000)),
+        );
+
+        // Before the window, at the boundaries, and after the window
+        assert!(!cert.authorizes(&subkey.public(), DelegationCapabilities::SIGN_RECORDS, 999));
+        assert!(cert.authorizes(&subkey.public(), DelegationCapabilities::SIGN_RECORDS, 1_000));
+        assert!(cert.authorizes(&subkey.public(), DelegationCapabilities::SIGN_RECORDS, 2_000));
+        assert!(!cert.authorizes(&subkey.public(), DelegationCapabilities::SIGN_RECORDS, 2_001));
+
+        // No validity window at all means no time restriction
+        let unbounded = master.delegate_to(subkey.public(), DelegationCapabilities::SIGN_RECORDS, None);
+        assert!(unbounded.authorizes(&subkey.public(), DelegationCapabilities::SIGN_RECORDS, 0));
+        assert!(unbounded.authorizes(&subkey.public(), DelegationCapabilities::SIGN_RECORDS, u64::MAX));
+    }
+}
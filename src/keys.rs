@@ -1,6 +1,31 @@
-use crate::Error;
+use crate::{Certificate, DelegationCapabilities, Error, InnerError};
 use base64::prelude::*;
-use ed25519_dalek::{SigningKey, VerifyingKey};
+use bech32::{FromBase32, ToBase32, Variant};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+/// Human-readable prefix for a `PublicKey` bech32 encoding
+pub const PUBLIC_KEY_HRP: &str = "mpub";
+
+/// Human-readable prefix for a `PrivateKey` bech32 encoding
+pub const PRIVATE_KEY_HRP: &str = "mprv";
+
+/// Human-readable prefix for a Nostr-compatible public key bech32 encoding
+pub const NOSTR_PUBLIC_KEY_HRP: &str = "npub";
+
+/// Human-readable prefix for a Nostr-compatible private key bech32 encoding
+pub const NOSTR_PRIVATE_KEY_HRP: &str = "nsec";
+
+fn bytes32_from_bech32(s: &str, expected_hrp: &str) -> Result<[u8; 32], Error> {
+    let (hrp, data, variant) = bech32::decode(s)?;
+    if hrp != expected_hrp {
+        return Err(InnerError::WrongBech32Hrp(hrp).into());
+    }
+    if variant != Variant::Bech32 {
+        return Err(InnerError::WrongBech32Variant.into());
+    }
+    let bytes = Vec::<u8>::from_base32(&data)?;
+    bytes.try_into().map_err(|_| InnerError::WrongLength.into())
+}
 
 /// A public signing key representing a server or user, whether a master key or subkey.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -25,9 +50,65 @@ impl PublicKey {
     // Convert a base64 `String` into a `PublicKey`
     pub fn from_printable(s: &str) -> Result<PublicKey, Error> {
         let bytes = BASE64_STANDARD.decode(s)?;
-        let vk = VerifyingKey::from_bytes(&bytes.try_into().unwrap())?;
+        let array: [u8; 32] = bytes.try_into().map_err(|_| InnerError::WrongLength)?;
+        let vk = VerifyingKey::from_bytes(&array)?;
         Ok(PublicKey(vk))
     }
+
+    /// Verify that `sig` is a valid signature over `msg` by this `PublicKey`
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the signature is invalid
+    pub fn verify(&self, msg: &[u8], sig: &Signature) -> Result<(), Error> {
+        self.0.verify(msg, sig)?;
+        Ok(())
+    }
+
+    /// Convert this `PublicKey` into a checksummed bech32 string with the given
+    /// human-readable prefix (e.g. [`PUBLIC_KEY_HRP`] or [`NOSTR_PUBLIC_KEY_HRP`])
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if `hrp` is not a valid bech32 human-readable prefix
+    pub fn to_bech32(&self, hrp: &str) -> Result<String, Error> {
+        Ok(bech32::encode(
+            hrp,
+            self.0.as_bytes().to_base32(),
+            Variant::Bech32,
+        )?)
+    }
+
+    /// Parse a checksummed bech32 string with the given expected human-readable
+    /// prefix into a `PublicKey`
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the string is not valid bech32, the checksum fails,
+    /// the human-readable prefix does not match `hrp`, or the decoded data is
+    /// not a valid `PublicKey`
+    pub fn from_bech32(s: &str, hrp: &str) -> Result<PublicKey, Error> {
+        let bytes = bytes32_from_bech32(s, hrp)?;
+        PublicKey::from_bytes(&bytes)
+    }
+
+    /// Convert this `PublicKey` into a Nostr-compatible `npub1...` string
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if bech32 encoding fails
+    pub fn to_npub(&self) -> Result<String, Error> {
+        self.to_bech32(NOSTR_PUBLIC_KEY_HRP)
+    }
+
+    /// Parse a Nostr-compatible `npub1...` string into a `PublicKey`
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err under the same conditions as [`PublicKey::from_bech32`]
+    pub fn from_npub(s: &str) -> Result<PublicKey, Error> {
+        Self::from_bech32(s, NOSTR_PUBLIC_KEY_HRP)
+    }
 }
 
 impl std::fmt::Display for PublicKey {
@@ -69,9 +150,85 @@ impl PrivateKey {
     // Convert a base64 `String` into a `PrivateKey`
     pub fn from_printable(s: &str) -> Result<PrivateKey, Error> {
         let bytes = BASE64_STANDARD.decode(s)?;
-        let sk = SigningKey::from_bytes(&bytes.try_into().unwrap());
+        let array: [u8; 32] = bytes.try_into().map_err(|_| InnerError::WrongLength)?;
+        let sk = SigningKey::from_bytes(&array);
         Ok(PrivateKey(sk))
     }
+
+    /// Sign a message with this `PrivateKey`
+    #[must_use]
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        self.0.sign(msg)
+    }
+
+    /// Convert this `PrivateKey` into a checksummed bech32 string with the given
+    /// human-readable prefix (e.g. [`PRIVATE_KEY_HRP`] or [`NOSTR_PRIVATE_KEY_HRP`])
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if `hrp` is not a valid bech32 human-readable prefix
+    pub fn to_bech32(&self, hrp: &str) -> Result<String, Error> {
+        Ok(bech32::encode(
+            hrp,
+            self.0.as_bytes().to_base32(),
+            Variant::Bech32,
+        )?)
+    }
+
+    /// Parse a checksummed bech32 string with the given expected human-readable
+    /// prefix into a `PrivateKey`
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if the string is not valid bech32, the checksum fails,
+    /// or the human-readable prefix does not match `hrp`
+    pub fn from_bech32(s: &str, hrp: &str) -> Result<PrivateKey, Error> {
+        let bytes = bytes32_from_bech32(s, hrp)?;
+        Ok(PrivateKey::from_bytes(&bytes))
+    }
+
+    /// Convert this `PrivateKey` into a Nostr-compatible `nsec1...` string
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if bech32 encoding fails
+    pub fn to_nsec(&self) -> Result<String, Error> {
+        self.to_bech32(NOSTR_PRIVATE_KEY_HRP)
+    }
+
+    /// Parse a Nostr-compatible `nsec1...` string into a `PrivateKey`
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err under the same conditions as [`PrivateKey::from_bech32`]
+    pub fn from_nsec(s: &str) -> Result<PrivateKey, Error> {
+        Self::from_bech32(s, NOSTR_PRIVATE_KEY_HRP)
+    }
+
+    /// Delegate `capabilities` to `subkey`, optionally restricted to the
+    /// inclusive `(start, end)` unix-second `valid_range`, by issuing a
+    /// `Certificate` signed by this (master) key.
+    ///
+    /// This lets a master key remain offline while a short-lived subkey
+    /// performs operational signing on its behalf.
+    #[must_use]
+    pub fn delegate_to(
+        &self,
+        subkey: PublicKey,
+        capabilities: DelegationCapabilities,
+        valid_range: Option<(u64, u64)>,
+    ) -> Certificate {
+        let master = self.public();
+        let bytes = Certificate::canonical_bytes(&master, &subkey, capabilities, valid_range);
+        let signature = self.sign(&bytes);
+        Certificate {
+            master,
+            subkey,
+            capabilities,
+            valid_range,
+            signature,
+        }
+    }
 }
 
 impl std::fmt::Display for PrivateKey {
@@ -80,6 +237,35 @@ impl std::fmt::Display for PrivateKey {
     }
 }
 
+/// Verify many `(message, signature, public key)` triples at once.
+///
+/// This amortizes the cost of verification across the whole batch by
+/// drawing a random 128-bit scalar per signature and checking a single
+/// combined group equation, rather than one equation per signature. It is
+/// significantly faster than calling [`PublicKey::verify`] in a loop when
+/// validating many records, e.g. an entire feed at once.
+///
+/// # Errors
+///
+/// Returns an Err if `messages`, `signatures`, and `public_keys` are not all
+/// the same length, or if any signature in the batch fails to verify. In
+/// the latter case the error does not identify which signature was at
+/// fault; callers that need to isolate a bad record should fall back to
+/// verifying each one individually.
+pub fn verify_batch(
+    messages: &[&[u8]],
+    signatures: &[Signature],
+    public_keys: &[PublicKey],
+) -> Result<(), Error> {
+    if messages.len() != signatures.len() || messages.len() != public_keys.len() {
+        return Err(InnerError::LengthMismatch.into());
+    }
+
+    let verifying_keys: Vec<VerifyingKey> = public_keys.iter().map(|pk| pk.0).collect();
+    ed25519_dalek::verify_batch(messages, signatures, &verifying_keys)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     #[test]
@@ -95,4 +281,115 @@ mod test {
         println!("public: {}", public_key);
         println!("private: {}", private_key);
     }
+
+    #[test]
+    fn test_sign_and_verify() {
+        use crate::PrivateKey;
+        use rand::rngs::OsRng;
+
+        let mut csprng = OsRng;
+
+        let private_key = PrivateKey::generate(&mut csprng);
+        let public_key = private_key.public();
+
+        let msg = b"hello world";
+        let sig = private_key.sign(msg);
+        assert!(public_key.verify(msg, &sig).is_ok());
+        assert!(public_key.verify(b"wrong message", &sig).is_err());
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        use super::verify_batch;
+        use crate::PrivateKey;
+        use rand::rngs::OsRng;
+
+        let mut csprng = OsRng;
+
+        let messages: Vec<&[u8]> = vec![b"message one", b"message two", b"message three"];
+        let private_keys: Vec<PrivateKey> =
+            messages.iter().map(|_| PrivateKey::generate(&mut csprng)).collect();
+        let public_keys: Vec<_> = private_keys.iter().map(PrivateKey::public).collect();
+        let signatures: Vec<_> = private_keys
+            .iter()
+            .zip(messages.iter())
+            .map(|(pk, msg)| pk.sign(msg))
+            .collect();
+
+        assert!(verify_batch(&messages, &signatures, &public_keys).is_ok());
+
+        let mut bad_signatures = signatures.clone();
+        bad_signatures.swap(0, 1);
+        assert!(verify_batch(&messages, &bad_signatures, &public_keys).is_err());
+    }
+
+    #[test]
+    fn test_verify_batch_length_mismatch() {
+        use super::verify_batch;
+        use crate::PrivateKey;
+        use rand::rngs::OsRng;
+
+        let mut csprng = OsRng;
+
+        let messages: Vec<&[u8]> = vec![b"message one", b"message two"];
+        let private_keys: Vec<PrivateKey> =
+            messages.iter().map(|_| PrivateKey::generate(&mut csprng)).collect();
+        let public_keys: Vec<_> = private_keys.iter().map(PrivateKey::public).collect();
+        let mut signatures: Vec<_> = private_keys
+            .iter()
+            .zip(messages.iter())
+            .map(|(pk, msg)| pk.sign(msg))
+            .collect();
+
+        // Drop one signature so the slice lengths no longer match
+        signatures.pop();
+
+        assert!(verify_batch(&messages, &signatures, &public_keys).is_err());
+    }
+
+    #[test]
+    fn test_bech32_roundtrip() {
+        use crate::{PrivateKey, PublicKey};
+        use rand::rngs::OsRng;
+
+        let mut csprng = OsRng;
+
+        let private_key = PrivateKey::generate(&mut csprng);
+        let public_key = private_key.public();
+
+        let pub_bech32 = public_key.to_bech32(super::PUBLIC_KEY_HRP).unwrap();
+        assert!(pub_bech32.starts_with("mpub1"));
+        assert_eq!(
+            PublicKey::from_bech32(&pub_bech32, super::PUBLIC_KEY_HRP).unwrap(),
+            public_key
+        );
+
+        let prv_bech32 = private_key.to_bech32(super::PRIVATE_KEY_HRP).unwrap();
+        assert!(prv_bech32.starts_with("mprv1"));
+        assert_eq!(
+            PrivateKey::from_bech32(&prv_bech32, super::PRIVATE_KEY_HRP)
+                .unwrap()
+                .as_bytes(),
+            private_key.as_bytes()
+        );
+
+        let npub = public_key.to_npub().unwrap();
+        assert!(npub.starts_with("npub1"));
+        assert_eq!(PublicKey::from_npub(&npub).unwrap(), public_key);
+
+        let nsec = private_key.to_nsec().unwrap();
+        assert!(nsec.starts_with("nsec1"));
+        assert_eq!(
+            PrivateKey::from_nsec(&nsec).unwrap().as_bytes(),
+            private_key.as_bytes()
+        );
+
+        // Wrong hrp is rejected
+        assert!(PublicKey::from_bech32(&pub_bech32, super::PRIVATE_KEY_HRP).is_err());
+
+        // Corrupted checksum is rejected
+        let mut corrupted = pub_bech32.clone();
+        corrupted.push('q');
+        assert!(PublicKey::from_bech32(&corrupted, super::PUBLIC_KEY_HRP).is_err());
+    }
 }
@@ -0,0 +1,64 @@
+use crate::{Error, InnerError, OwnedTag};
+
+/// A query over a `TagSet`, expressed in disjunctive normal form: a
+/// disjunction ("OR") of conjunctions ("AND-sets") of required tags.
+///
+/// A `TagSet` matches the query if it contains every tag in at least one
+/// of the AND-sets. This is the shape subscribers typically want: "give me
+/// records tagged (A and B) or tagged (C)".
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagQuery(Vec<Vec<OwnedTag>>);
+
+impl TagQuery {
+    /// Create a new, empty `TagQuery`
+    ///
+    /// An empty query matches nothing until at least one AND-set is added.
+    #[must_use]
+    pub fn new() -> TagQuery {
+        TagQuery(Vec::new())
+    }
+
+    /// Add a conjunction (AND-set) of required tags to this query
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err if `tags` is empty. An empty AND-set is vacuously
+    /// satisfied by every `TagSet` (matching requires *all* tags in some
+    /// AND-set to be present, and "all of zero tags" is trivially true), so
+    /// accepting one would silently turn a malformed or buggy subscriber
+    /// filter into "match everything" instead of rejecting it.
+    pub fn add_and_set<I>(&mut self, tags: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = OwnedTag>,
+    {
+        let and_set: Vec<OwnedTag> = tags.into_iter().collect();
+        if and_set.is_empty() {
+            return Err(InnerError::EmptyAndSet.into());
+        }
+        self.0.push(and_set);
+        Ok(())
+    }
+
+    /// The AND-sets making up this query
+    #[must_use]
+    pub fn and_sets(&self) -> &[Vec<OwnedTag>] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TagType;
+
+    #[test]
+    fn test_add_and_set_rejects_empty() {
+        let mut query = TagQuery::new();
+        assert!(query.add_and_set(Vec::<OwnedTag>::new()).is_err());
+        assert!(query.and_sets().is_empty());
+
+        let t1 = OwnedTag::new(TagType(1), b"one").unwrap();
+        assert!(query.add_and_set([t1]).is_ok());
+        assert_eq!(query.and_sets().len(), 1);
+    }
+}
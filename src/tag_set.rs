@@ -1,6 +1,5 @@
-use crate::{Error, InnerError, Tag};
-#[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
+use crate::{Error, InnerError, Tag, TagQuery};
+use std::collections::HashSet;
 use std::ops::{Deref, DerefMut};
 
 /// A sequence of `Tag`s, borrowed
@@ -78,6 +77,108 @@ impl TagSet {
             p: 0,
         }
     }
+
+    /// Does this `TagSet` contain `tag`?
+    ///
+    /// Tags are compared by the full bytes of their encoded form.
+    #[must_use]
+    pub fn contains(&self, tag: &Tag) -> bool {
+        self.iter().any(|t| t.as_bytes() == tag.as_bytes())
+    }
+
+    /// A set of this `TagSet`'s tags, keyed by their full encoded bytes, for
+    /// O(1) membership checks when comparing against many tags at once.
+    fn byte_set(&self) -> HashSet<&[u8]> {
+        self.iter().map(Tag::as_bytes).collect()
+    }
+
+    /// Is every tag in this `TagSet` also present in `other`?
+    ///
+    /// Linear in the size of both tag sets: `other` is indexed once, then
+    /// each of this set's tags is looked up in it.
+    #[must_use]
+    pub fn is_subset(&self, other: &TagSet) -> bool {
+        let other_bytes = other.byte_set();
+        self.iter().all(|tag| other_bytes.contains(tag.as_bytes()))
+    }
+
+    /// Does this `TagSet` share at least one tag with `other`?
+    ///
+    /// Linear in the size of both tag sets: `other` is indexed once, then
+    /// each of this set's tags is looked up in it.
+    #[must_use]
+    pub fn intersects(&self, other: &TagSet) -> bool {
+        let other_bytes = other.byte_set();
+        self.iter().any(|tag| other_bytes.contains(tag.as_bytes()))
+    }
+
+    /// The tags present in this `TagSet`, in `other`, or in both
+    ///
+    /// Linear in the size of both tag sets: this set's tags are indexed
+    /// once, then each of `other`'s tags is looked up in it.
+    #[must_use]
+    pub fn union(&self, other: &TagSet) -> OwnedTagSet {
+        let self_bytes = self.byte_set();
+        let mut out = self.to_owned();
+        for tag in other.iter() {
+            if !self_bytes.contains(tag.as_bytes()) {
+                out.add_tag(tag);
+            }
+        }
+        out
+    }
+
+    /// The tags present in both this `TagSet` and `other`
+    ///
+    /// Linear in the size of both tag sets: `other` is indexed once, then
+    /// each of this set's tags is looked up in it.
+    #[must_use]
+    pub fn intersection(&self, other: &TagSet) -> OwnedTagSet {
+        let other_bytes = other.byte_set();
+        let mut out = OwnedTagSet::new();
+        for tag in self.iter() {
+            if other_bytes.contains(tag.as_bytes()) {
+                out.add_tag(tag);
+            }
+        }
+        out
+    }
+
+    /// The tags present in this `TagSet` but not in `other`
+    ///
+    /// Linear in the size of both tag sets: `other` is indexed once, then
+    /// each of this set's tags is looked up in it.
+    #[must_use]
+    pub fn difference(&self, other: &TagSet) -> OwnedTagSet {
+        let other_bytes = other.byte_set();
+        let mut out = OwnedTagSet::new();
+        for tag in self.iter() {
+            if !other_bytes.contains(tag.as_bytes()) {
+                out.add_tag(tag);
+            }
+        }
+        out
+    }
+
+    /// Does this `TagSet` satisfy `query`?
+    ///
+    /// A `TagQuery` is a disjunction of conjunctions ("AND-sets") of
+    /// required tags; this matches if this `TagSet` contains every tag in
+    /// at least one of those AND-sets. This lets a relay test a record's
+    /// tags against a subscriber's filter in one call.
+    ///
+    /// Linear in the size of this tag set and the query: this set's tags
+    /// are indexed once, then each AND-set's tags are looked up in it,
+    /// rather than re-scanning this set per query tag.
+    #[must_use]
+    pub fn matches(&self, query: &TagQuery) -> bool {
+        let self_bytes = self.byte_set();
+        query.and_sets().iter().any(|and_set| {
+            and_set
+                .iter()
+                .all(|tag| self_bytes.contains(tag.as_bytes()))
+        })
+    }
 }
 
 impl<'a> IntoIterator for &'a TagSet {
@@ -113,7 +214,6 @@ impl<'a> Iterator for TagSetIter<'a> {
 ///
 /// See `TagSet` for the borrowed variant.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct OwnedTagSet(Vec<u8>);
 
 /// Empty `TagSet`
@@ -176,6 +276,118 @@ impl AsMut<TagSet> for OwnedTagSet {
     }
 }
 
+/// Structured serde support for `OwnedTagSet`.
+///
+/// A tag set is serialized as a sequence of `{ "type": <u16>, "data": ... }`
+/// entries, one per tag, rather than as the opaque wire bytes. `data` is a
+/// base64 string for human-readable formats (JSON, etc.) and a plain byte
+/// sequence for compact binary formats (CBOR, bincode, etc.), matching
+/// `Serializer::is_human_readable`. Deserializing re-encodes each entry
+/// into the length-prefixed wire form and validates the result through
+/// `TagSet::from_bytes`.
+#[cfg(feature = "serde")]
+mod tag_set_serde {
+    use super::{OwnedTagSet, TagSet};
+    use crate::{OwnedTag, Tag, TagType};
+    use base64::prelude::*;
+    use serde::de::{self, SeqAccess, Visitor};
+    use serde::ser::{SerializeSeq, SerializeStruct};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::fmt;
+
+    struct TagEntryRef<'a>(&'a Tag);
+
+    // Forces a `&[u8]` through `Serializer::serialize_bytes` rather than the
+    // generic slice impl (which formats/CBOR/bincode treat as a sequence of
+    // individual integers, not a compact byte string).
+    struct RawBytes<'a>(&'a [u8]);
+
+    impl Serialize for RawBytes<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    impl Serialize for TagEntryRef<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let human_readable = serializer.is_human_readable();
+            let mut state = serializer.serialize_struct("Tag", 2)?;
+            state.serialize_field("type", &self.0.get_type().0)?;
+            if human_readable {
+                state.serialize_field("data", &BASE64_STANDARD.encode(self.0.data_bytes()))?;
+            } else {
+                state.serialize_field("data", &RawBytes(self.0.data_bytes()))?;
+            }
+            state.end()
+        }
+    }
+
+    impl Serialize for OwnedTagSet {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let tag_set: &TagSet = self;
+            let tags: Vec<&Tag> = tag_set.iter().collect();
+            let mut seq = serializer.serialize_seq(Some(tags.len()))?;
+            for tag in tags {
+                seq.serialize_element(&TagEntryRef(tag))?;
+            }
+            seq.end()
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum TagDataEntry {
+        Bytes(Vec<u8>),
+        Base64(String),
+    }
+
+    impl TagDataEntry {
+        fn into_bytes<E: de::Error>(self) -> Result<Vec<u8>, E> {
+            match self {
+                TagDataEntry::Bytes(b) => Ok(b),
+                TagDataEntry::Base64(s) => BASE64_STANDARD.decode(s).map_err(de::Error::custom),
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct TagEntryOwned {
+        #[serde(rename = "type")]
+        kind: u16,
+        data: TagDataEntry,
+    }
+
+    struct OwnedTagSetVisitor;
+
+    impl<'de> Visitor<'de> for OwnedTagSetVisitor {
+        type Value = OwnedTagSet;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a sequence of { type, data } tag entries")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut bytes: Vec<u8> = Vec::new();
+            while let Some(entry) = seq.next_element::<TagEntryOwned>()? {
+                let data = entry.data.into_bytes()?;
+                let owned_tag =
+                    OwnedTag::new(TagType(entry.kind), &data).map_err(de::Error::custom)?;
+                bytes.extend(owned_tag.as_bytes());
+            }
+            if !bytes.is_empty() {
+                TagSet::from_bytes(&bytes).map_err(de::Error::custom)?;
+            }
+            Ok(OwnedTagSet(bytes))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for OwnedTagSet {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_seq(OwnedTagSetVisitor)
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -264,4 +476,119 @@ mod test {
 
         let _owned_tag_set = OwnedTagSet::from_tags(tags.iter().map(|t| &**t));
     }
+
+    #[test]
+    fn test_tag_set_algebra() {
+        let t1 = OwnedTag::new(TagType(1), b"one").unwrap();
+        let t2 = OwnedTag::new(TagType(2), b"two").unwrap();
+        let t3 = OwnedTag::new(TagType(3), b"three").unwrap();
+
+        let mut set_a = OwnedTagSet::new();
+        set_a.add_tag(&t1);
+        set_a.add_tag(&t2);
+
+        let mut set_b = OwnedTagSet::new();
+        set_b.add_tag(&t2);
+        set_b.add_tag(&t3);
+
+        assert!(set_a.contains(&t1));
+        assert!(!set_a.contains(&t3));
+
+        assert!(set_a.intersects(&set_b));
+        assert!(!set_a.is_subset(&set_b));
+
+        let mut only_t2 = OwnedTagSet::new();
+        only_t2.add_tag(&t2);
+        assert!(only_t2.is_subset(&set_a));
+
+        let union = set_a.union(&set_b);
+        assert!(union.contains(&t1));
+        assert!(union.contains(&t2));
+        assert!(union.contains(&t3));
+
+        let intersection = set_a.intersection(&set_b);
+        assert!(intersection.contains(&t2));
+        assert!(!intersection.contains(&t1));
+        assert!(!intersection.contains(&t3));
+
+        let difference = set_a.difference(&set_b);
+        assert!(difference.contains(&t1));
+        assert!(!difference.contains(&t2));
+    }
+
+    #[test]
+    fn test_tag_set_matches_query() {
+        use crate::TagQuery;
+
+        let t1 = OwnedTag::new(TagType(1), b"one").unwrap();
+        let t2 = OwnedTag::new(TagType(2), b"two").unwrap();
+        let t3 = OwnedTag::new(TagType(3), b"three").unwrap();
+
+        let mut set = OwnedTagSet::new();
+        set.add_tag(&t1);
+        set.add_tag(&t2);
+
+        let mut query = TagQuery::new();
+        query.add_and_set([t1.clone(), t3.clone()]).unwrap();
+        query.add_and_set([t2.clone()]).unwrap();
+
+        assert!(set.matches(&query));
+
+        let mut unmatched_query = TagQuery::new();
+        unmatched_query.add_and_set([t3]).unwrap();
+        assert!(!set.matches(&unmatched_query));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_owned_tag_set_serde_json_roundtrip() {
+        let t1 = OwnedTag::new(TagType(1), b"one").unwrap();
+        let t2 = OwnedTag::new(TagType(2), b"two").unwrap();
+
+        let mut tag_set = OwnedTagSet::new();
+        tag_set.add_tag(&t1);
+        tag_set.add_tag(&t2);
+
+        let json = serde_json::to_string(&tag_set).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"type":1,"data":"b25l"},{"type":2,"data":"dHdv"}]"#
+        );
+
+        let roundtripped: OwnedTagSet = serde_json::from_str(&json).unwrap();
+        assert_eq!(roundtripped, tag_set);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_owned_tag_set_serde_cbor_roundtrip() {
+        let t1 = OwnedTag::new(TagType(1), b"one").unwrap();
+        let t2 = OwnedTag::new(TagType(2), b"two").unwrap();
+
+        let mut tag_set = OwnedTagSet::new();
+        tag_set.add_tag(&t1);
+        tag_set.add_tag(&t2);
+
+        // CBOR is not human-readable, so `data` is encoded as a compact
+        // byte string rather than a base64 string.
+        let cbor = serde_cbor::to_vec(&tag_set).unwrap();
+        let roundtripped: OwnedTagSet = serde_cbor::from_slice(&cbor).unwrap();
+        assert_eq!(roundtripped, tag_set);
+
+        let value: serde_cbor::Value = serde_cbor::from_slice(&cbor).unwrap();
+        if let serde_cbor::Value::Array(entries) = value {
+            for entry in entries {
+                if let serde_cbor::Value::Map(fields) = entry {
+                    let data = fields
+                        .get(&serde_cbor::Value::Text("data".to_string()))
+                        .unwrap();
+                    assert!(matches!(data, serde_cbor::Value::Bytes(_)));
+                } else {
+                    panic!("expected a map entry");
+                }
+            }
+        } else {
+            panic!("expected an array");
+        }
+    }
 }
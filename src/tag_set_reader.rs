@@ -0,0 +1,152 @@
+use crate::{Error, InnerError, OwnedTag, OwnedTagSet, Tag, TagSet};
+use std::io::Read;
+
+/// Reads `Tag`s one at a time from an `io::Read`, without requiring the
+/// whole tag set to already be buffered in a contiguous slice.
+///
+/// This is for decoding tag sets off a framed transport (e.g. a socket)
+/// where many tag sets are concatenated back to back: each `read_tag` call
+/// advances the underlying reader by exactly one `Tag`, leaving it
+/// positioned at the next byte, so pre-slicing is never required.
+pub struct TagSetReader<R: Read> {
+    inner: R,
+}
+
+impl<R: Read> TagSetReader<R> {
+    /// Wrap a reader to pull `Tag`s from it one at a time
+    pub fn new(inner: R) -> TagSetReader<R> {
+        TagSetReader { inner }
+    }
+
+    /// Read one length-prefixed `Tag` from the stream.
+    ///
+    /// Returns `Ok(None)` if the stream is cleanly exhausted before any
+    /// bytes of a new tag arrive.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InnerError::EndOfInput` if the tag's length header is
+    /// itself incomplete, or `InnerError::Truncated` if the header
+    /// promises more bytes than the stream goes on to yield.
+    pub fn read_tag(&mut self) -> Result<Option<OwnedTag>, Error> {
+        let mut len_bytes = [0u8; 2];
+        match read_fill(&mut self.inner, &mut len_bytes)? {
+            0 => return Ok(None),
+            n if n < len_bytes.len() => return Err(InnerError::EndOfInput.into()),
+            _ => {}
+        }
+
+        let len = u16::from_le_bytes(len_bytes) as usize;
+        if len < len_bytes.len() {
+            return Err(InnerError::EndOfInput.into());
+        }
+
+        let mut full = Vec::with_capacity(len);
+        full.extend_from_slice(&len_bytes);
+        full.resize(len, 0);
+        let remaining = &mut full[len_bytes.len()..];
+        if read_fill(&mut self.inner, remaining)? < remaining.len() {
+            return Err(InnerError::Truncated.into());
+        }
+
+        let tag = Tag::from_bytes(&full)?;
+        Ok(Some(tag.to_owned()))
+    }
+
+    /// Read exactly one tag set of `expected_len` bytes, consuming those
+    /// bytes from the stream and returning them as an `OwnedTagSet`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InnerError::Truncated` if the stream yields fewer than
+    /// `expected_len` bytes, or any error `TagSet::from_bytes` would
+    /// return for malformed contents.
+    pub fn read_tag_set(&mut self, expected_len: usize) -> Result<OwnedTagSet, Error> {
+        let mut buf = vec![0u8; expected_len];
+        if read_fill(&mut self.inner, &mut buf)? < expected_len {
+            return Err(InnerError::Truncated.into());
+        }
+        Ok(TagSet::from_bytes(&buf)?.to_owned())
+    }
+}
+
+impl<R: Read> Iterator for TagSetReader<R> {
+    type Item = Result<OwnedTag, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_tag().transpose()
+    }
+}
+
+// Like `Read::read_exact`, but treats a clean EOF with zero bytes read as
+// success (returning the short count) instead of an error, so callers can
+// tell "nothing left to read" apart from "stopped partway through".
+fn read_fill<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, Error> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(InnerError::Io(e).into()),
+        }
+    }
+    Ok(read)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TagType;
+    use std::io::Cursor;
+
+    fn example_bytes() -> Vec<u8> {
+        vec![
+            8, 0, // length
+            1, 0, // type 1,
+            10, 9, 8, 7, // data
+            10, 0, // length
+            2, 0, // type 2
+            1, 2, 3, 4, 5, 6, // data
+        ]
+    }
+
+    #[test]
+    fn test_read_tag_one_at_a_time() {
+        let bytes = example_bytes();
+        let mut reader = TagSetReader::new(Cursor::new(bytes));
+
+        let tag0 = reader.read_tag().unwrap().unwrap();
+        assert_eq!(tag0.get_type(), TagType(1));
+        assert_eq!(tag0.data_bytes(), &[10, 9, 8, 7]);
+
+        let tag1 = reader.read_tag().unwrap().unwrap();
+        assert_eq!(tag1.get_type(), TagType(2));
+        assert_eq!(tag1.data_bytes(), &[1, 2, 3, 4, 5, 6]);
+
+        assert!(reader.read_tag().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_read_tag_set() {
+        let bytes = example_bytes();
+        let len = bytes.len();
+        let mut reader = TagSetReader::new(Cursor::new(bytes));
+
+        let tag_set = reader.read_tag_set(len).unwrap();
+        let mut iter = tag_set.iter();
+        assert_eq!(iter.next().unwrap().get_type(), TagType(1));
+        assert_eq!(iter.next().unwrap().get_type(), TagType(2));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_read_tag_truncated() {
+        let mut bytes = example_bytes();
+        bytes.truncate(12); // cut off after the second tag's type, before its data
+        let mut reader = TagSetReader::new(Cursor::new(bytes));
+
+        let _ = reader.read_tag().unwrap().unwrap();
+        assert!(reader.read_tag().is_err());
+    }
+}
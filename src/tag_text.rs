@@ -0,0 +1,76 @@
+//! Unicode normalization for text-bearing tags, behind the
+//! `unicode-normalization` feature.
+
+#[cfg(feature = "unicode-normalization")]
+use crate::{Error, OwnedTag, OwnedTagSet, TagType};
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::UnicodeNormalization;
+
+#[cfg(feature = "unicode-normalization")]
+impl OwnedTag {
+    /// Create a new text-bearing tag, applying Unicode NFC normalization to
+    /// `text` before encoding it.
+    ///
+    /// Two visually identical strings can be composed differently (e.g. "é"
+    /// as one codepoint vs. "e" followed by a combining acute accent), which
+    /// otherwise produces different tag bytes and therefore different
+    /// equality, hashing, and `TagSet` matching results for the same text.
+    /// Normalizing to NFC first keeps those comparisons consistent across
+    /// clients that compose characters differently, which matters once
+    /// these tags feed content-addressed references.
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err under the same conditions as [`OwnedTag::new`]
+    pub fn new_text_normalized(tag_type: TagType, text: &str) -> Result<OwnedTag, Error> {
+        let normalized: String = text.nfc().collect();
+        OwnedTag::new(tag_type, normalized.as_bytes())
+    }
+}
+
+#[cfg(feature = "unicode-normalization")]
+impl OwnedTagSet {
+    /// Add a text-bearing tag to this set, applying Unicode NFC
+    /// normalization to `text` before encoding it.
+    ///
+    /// See [`OwnedTag::new_text_normalized`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an Err under the same conditions as [`OwnedTag::new`]
+    pub fn add_text_tag_normalized(&mut self, tag_type: TagType, text: &str) -> Result<(), Error> {
+        let tag = OwnedTag::new_text_normalized(tag_type, text)?;
+        self.add_tag(&tag);
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "unicode-normalization"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new_text_normalized_matches_precomposed() {
+        let decomposed = "e\u{0301}"; // "e" + combining acute accent
+        let precomposed = "\u{00e9}"; // "é", single codepoint
+
+        let t1 = OwnedTag::new_text_normalized(TagType(1), decomposed).unwrap();
+        let t2 = OwnedTag::new_text_normalized(TagType(1), precomposed).unwrap();
+
+        assert_eq!(t1, t2);
+        assert_eq!(t1.as_bytes(), t2.as_bytes());
+    }
+
+    #[test]
+    fn test_add_text_tag_normalized() {
+        use crate::OwnedTagSet;
+
+        let mut set1 = OwnedTagSet::new();
+        set1.add_text_tag_normalized(TagType(1), "e\u{0301}").unwrap();
+
+        let mut set2 = OwnedTagSet::new();
+        set2.add_text_tag_normalized(TagType(1), "\u{00e9}").unwrap();
+
+        assert_eq!(set1, set2);
+    }
+}